@@ -0,0 +1,74 @@
+//! Conversions to/from `chrono`'s `NaiveTime`.
+
+use chrono::NaiveTime;
+use chrono::Timelike;
+
+use crate::WallClockTime;
+
+impl From<WallClockTime> for NaiveTime {
+  /// Converts a [`WallClockTime`] into a `chrono` [`NaiveTime`].
+  ///
+  /// A leap second (see [`WallClockTime::is_leap_second`]) is represented the way `chrono` itself
+  /// represents one: as second `59` with a nanosecond component in the range
+  /// `1_000_000_000..2_000_000_000`.
+  fn from(t: WallClockTime) -> Self {
+    let (second, nanos) =
+      if t.is_leap_second() { (59, t.nanosecond() + 1_000_000_000) } else { (t.second(), t.nanosecond()) };
+    NaiveTime::from_hms_nano_opt(t.hour() as u32, t.minute() as u32, second as u32, nanos)
+      .expect("a WallClockTime always maps to a valid NaiveTime")
+  }
+}
+
+impl TryFrom<NaiveTime> for WallClockTime {
+  type Error = &'static str;
+
+  /// Converts a `chrono` [`NaiveTime`] into a [`WallClockTime`].
+  ///
+  /// A `chrono` leap second (a nanosecond component of `1_000_000_000` or higher) round-trips to
+  /// [`WallClockTime::is_leap_second`].
+  fn try_from(t: NaiveTime) -> Result<Self, Self::Error> {
+    let nanos = t.nanosecond();
+    if nanos >= 1_000_000_000 {
+      WallClockTime::try_new_with_nanos(t.hour() as u8, t.minute() as u8, 60, nanos - 1_000_000_000)
+    } else {
+      WallClockTime::try_new_with_nanos(t.hour() as u8, t.minute() as u8, t.second() as u8, nanos)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+  use chrono::NaiveTime;
+
+  use crate::time;
+  use crate::WallClockTime;
+
+  #[test]
+  fn test_to_naive_time() {
+    let naive: NaiveTime = time!(15:04:05).into();
+    check!(naive == NaiveTime::from_hms_opt(15, 4, 5).unwrap());
+  }
+
+  #[test]
+  fn test_from_naive_time() -> Result<(), &'static str> {
+    let naive = NaiveTime::from_hms_opt(15, 4, 5).unwrap();
+    check!(WallClockTime::try_from(naive)? == time!(15:04:05));
+    Ok(())
+  }
+
+  #[test]
+  fn test_leap_second_round_trip() -> Result<(), &'static str> {
+    let leap = WallClockTime::try_new(23, 59, 60)?;
+    let naive: NaiveTime = leap.into();
+    check!(WallClockTime::try_from(naive)? == leap);
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_rejects_hour_outside_naive_time_range() {
+    // `WallClockTime`'s public constructors and parsers all enforce `hour < 24`, so there's no
+    // way to reach `From<WallClockTime> for NaiveTime` with an out-of-range value to convert.
+    check!("25:00:00".parse::<WallClockTime>().is_err());
+  }
+}
@@ -14,7 +14,7 @@ use crate::WallClockTime;
 
 impl ToSql<sql_types::Time, Pg> for WallClockTime {
   fn to_sql<'se>(&'se self, out: &mut Output<'se, '_, Pg>) -> SerializeResult {
-    let micros = self.seconds as i64 * 1_000_000 + self.micros as i64;
+    let micros = self.seconds as i64 * 1_000_000 + self.microsecond() as i64;
     ToSql::<sql_types::Time, Pg>::to_sql(&PgTime(micros), &mut out.reborrow())
   }
 }
@@ -22,6 +22,10 @@ impl ToSql<sql_types::Time, Pg> for WallClockTime {
 impl FromSql<sql_types::Time, Pg> for WallClockTime {
   fn from_sql(bytes: PgValue<'_>) -> DeserializeResult<Self> {
     let PgTime(offset) = FromSql::<diesel::sql_types::Time, Pg>::from_sql(bytes)?;
-    Ok(Self { seconds: (offset / 1_000_000) as u32, micros: (offset % 1_000_000) as u32 })
+    Ok(Self {
+      seconds: (offset / 1_000_000) as u32,
+      nanos: (offset % 1_000_000) as u32 * 1_000,
+      leap: false,
+    })
   }
 }
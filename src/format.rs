@@ -0,0 +1,379 @@
+//! `strftime`/`strptime`-style formatting and parsing for [`WallClockTime`].
+
+use crate::WallClockTime;
+
+/// A single component of a parsed format string.
+enum Token<'a> {
+  /// Literal text that must appear verbatim.
+  Literal(&'a str),
+  /// `%H`: the hour, 24-hour, zero-padded to two digits.
+  Hour24,
+  /// `%I`: the hour, 12-hour, zero-padded to two digits.
+  Hour12,
+  /// `%p`: `AM` or `PM`.
+  AmPm,
+  /// `%M`: the minute, zero-padded to two digits.
+  Minute,
+  /// `%S`: the second, zero-padded to two digits.
+  Second,
+  /// `%f`: the microsecond, zero-padded to six digits.
+  Micros,
+  /// `%.Nf`: a literal `.` followed by exactly `N` subsecond digits.
+  FixedFrac(u8),
+}
+
+/// Breaks a `strftime`-style format string into a sequence of literal and specifier tokens.
+fn tokenize(fmt: &str) -> Result<Vec<Token<'_>>, &'static str> {
+  let mut tokens = Vec::new();
+  let bytes = fmt.as_bytes();
+  let mut i = 0;
+  while i < bytes.len() {
+    if bytes[i] != b'%' {
+      let start = i;
+      while i < bytes.len() && bytes[i] != b'%' {
+        i += 1;
+      }
+      tokens.push(Token::Literal(&fmt[start..i]));
+      continue;
+    }
+    i += 1;
+    match bytes.get(i) {
+      Some(b'H') => {
+        tokens.push(Token::Hour24);
+        i += 1;
+      }
+      Some(b'I') => {
+        tokens.push(Token::Hour12);
+        i += 1;
+      }
+      Some(b'p') => {
+        tokens.push(Token::AmPm);
+        i += 1;
+      }
+      Some(b'M') => {
+        tokens.push(Token::Minute);
+        i += 1;
+      }
+      Some(b'S') => {
+        tokens.push(Token::Second);
+        i += 1;
+      }
+      Some(b'f') => {
+        tokens.push(Token::Micros);
+        i += 1;
+      }
+      Some(b'.') => {
+        let digits = *bytes.get(i + 1).ok_or("Invalid format specifier")?;
+        if !matches!(digits, b'3' | b'6' | b'9') || bytes.get(i + 2) != Some(&b'f') {
+          Err("Invalid format specifier")?;
+        }
+        tokens.push(Token::FixedFrac(digits - b'0'));
+        i += 3;
+      }
+      _ => Err("Unknown format specifier")?,
+    }
+  }
+  Ok(tokens)
+}
+
+/// Converts a 24-hour hour into its 12-hour equivalent (both `0` and `12` become `12`).
+pub(crate) const fn hour_12(hour: u8) -> u8 {
+  match hour % 12 {
+    0 => 12,
+    h => h,
+  }
+}
+
+/// Takes up to `max` ASCII digit characters from the start of `s`, returning the digits and the
+/// remainder. At least one digit is required.
+fn take_digits(s: &str, max: usize) -> Result<(&str, &str), &'static str> {
+  let end = s.bytes().take(max).take_while(u8::is_ascii_digit).count();
+  if end == 0 {
+    Err("Expected a digit")?;
+  }
+  Ok(s.split_at(end))
+}
+
+/// Controls how many fractional-second digits [`WallClockTime::display_with`] renders.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SubsecondFormat {
+  /// Render no fractional seconds at all.
+  None,
+  /// Render exactly 3 fractional digits (milliseconds).
+  Millis,
+  /// Render exactly 6 fractional digits (microseconds).
+  Micros,
+  /// Render exactly 9 fractional digits (nanoseconds).
+  Nanos,
+  /// Render as many fractional digits as `Nanos` would, but trim trailing zeros, and omit the
+  /// `.` entirely if there's no fractional part at all.
+  AutoTrim,
+}
+
+impl WallClockTime {
+  /// Formats this wall-clock time as `HH:MM:SS`, with the fractional-second portion controlled by
+  /// `format`.
+  ///
+  /// ## Examples
+  ///
+  /// ```
+  /// # use wall_clock::{SubsecondFormat, WallClockTime};
+  /// let t = WallClockTime::new_with_nanos(15, 4, 5, 123_000_000);
+  /// assert_eq!(t.display_with(SubsecondFormat::Millis), "15:04:05.123");
+  /// assert_eq!(t.display_with(SubsecondFormat::None), "15:04:05");
+  /// assert_eq!(t.display_with(SubsecondFormat::AutoTrim), "15:04:05.123");
+  /// ```
+  pub fn display_with(&self, format: SubsecondFormat) -> String {
+    let mut s = format!("{:02}:{:02}:{:02}", self.hour(), self.minute(), self.second());
+    let digits = match format {
+      SubsecondFormat::None => return s,
+      SubsecondFormat::Millis => 3,
+      SubsecondFormat::Micros => 6,
+      SubsecondFormat::Nanos => 9,
+      SubsecondFormat::AutoTrim if self.nanosecond() == 0 => return s,
+      SubsecondFormat::AutoTrim => 9,
+    };
+    let full = format!("{:09}", self.nanosecond());
+    let mut frac = &full[..digits];
+    if format == SubsecondFormat::AutoTrim {
+      frac = frac.trim_end_matches('0');
+    }
+    s.push('.');
+    s.push_str(frac);
+    s
+  }
+
+  /// Formats this wall-clock time according to a `strftime`-style format string.
+  ///
+  /// Supported specifiers: `%H` (24-hour), `%I`/`%p` (12-hour + AM/PM), `%M`, `%S`, `%f` (6-digit
+  /// microseconds), and `%.3f`/`%.6f`/`%.9f` (a literal `.` plus a fixed number of subsecond
+  /// digits). Any other text is copied through literally.
+  ///
+  /// ## Examples
+  ///
+  /// ```
+  /// # use wall_clock::time;
+  /// assert_eq!(time!(15:04:05).format("%H:%M"), "15:04");
+  /// assert_eq!(time!(15:04:05).format("%I:%M %p"), "03:04 PM");
+  /// ```
+  ///
+  /// ## Panics
+  ///
+  /// Panics if `fmt` contains an unrecognized `%` specifier.
+  pub fn format(&self, fmt: &str) -> String {
+    let tokens = tokenize(fmt).expect("invalid format string");
+    let mut out = String::new();
+    for token in tokens {
+      match token {
+        Token::Literal(s) => out.push_str(s),
+        Token::Hour24 => out.push_str(&format!("{:02}", self.hour())),
+        Token::Hour12 => out.push_str(&format!("{:02}", hour_12(self.hour()))),
+        Token::AmPm => out.push_str(if self.hour() < 12 { "AM" } else { "PM" }),
+        Token::Minute => out.push_str(&format!("{:02}", self.minute())),
+        Token::Second => out.push_str(&format!("{:02}", self.second())),
+        Token::Micros => out.push_str(&format!("{:06}", self.microsecond())),
+        Token::FixedFrac(n) => {
+          out.push('.');
+          out.push_str(&format!("{:09}", self.nanosecond())[..n as usize]);
+        }
+      }
+    }
+    out
+  }
+
+  /// Parses a wall-clock time from `s` according to a `strftime`-style format string.
+  ///
+  /// See [`WallClockTime::format`] for the supported specifiers.
+  pub fn parse_from_str(s: &str, fmt: &str) -> Result<Self, &'static str> {
+    let tokens = tokenize(fmt)?;
+    let mut rest = s;
+    let mut hour = None;
+    let mut hour_is_12 = false;
+    let mut pm = false;
+    let mut minute = 0u8;
+    let mut second = 0u8;
+    let mut nanos = 0u32;
+    for token in tokens {
+      match token {
+        Token::Literal(lit) => rest = rest.strip_prefix(lit).ok_or("Literal text did not match")?,
+        Token::Hour24 => {
+          let (digits, remainder) = take_digits(rest, 2)?;
+          hour = Some(digits.parse::<u8>().map_err(|_| "Invalid hour")?);
+          rest = remainder;
+        }
+        Token::Hour12 => {
+          let (digits, remainder) = take_digits(rest, 2)?;
+          hour = Some(digits.parse::<u8>().map_err(|_| "Invalid hour")?);
+          hour_is_12 = true;
+          rest = remainder;
+        }
+        Token::AmPm => {
+          if let Some(remainder) = rest.strip_prefix("PM").or_else(|| rest.strip_prefix("pm")) {
+            pm = true;
+            rest = remainder;
+          } else if let Some(remainder) = rest.strip_prefix("AM").or_else(|| rest.strip_prefix("am")) {
+            rest = remainder;
+          } else {
+            Err("Expected AM or PM")?;
+          }
+        }
+        Token::Minute => {
+          let (digits, remainder) = take_digits(rest, 2)?;
+          minute = digits.parse::<u8>().map_err(|_| "Invalid minute")?;
+          rest = remainder;
+        }
+        Token::Second => {
+          let (digits, remainder) = take_digits(rest, 2)?;
+          second = digits.parse::<u8>().map_err(|_| "Invalid second")?;
+          rest = remainder;
+        }
+        Token::Micros => {
+          let (digits, remainder) = take_digits(rest, 6)?;
+          let micros = digits.parse::<u32>().map_err(|_| "Invalid microseconds")?;
+          nanos = micros * 1_000;
+          rest = remainder;
+        }
+        Token::FixedFrac(n) => {
+          rest = rest.strip_prefix('.').ok_or("Expected '.'")?;
+          let (digits, remainder) = take_digits(rest, n as usize)?;
+          if digits.len() != n as usize {
+            Err("Invalid subseconds")?;
+          }
+          nanos = format!("{digits:0<9}").parse().map_err(|_| "Invalid subseconds")?;
+          rest = remainder;
+        }
+      }
+    }
+    if !rest.is_empty() {
+      Err("Trailing characters after format")?;
+    }
+    let mut hour = hour.ok_or("Format string did not specify an hour")?;
+    if hour_is_12 {
+      if !(1..=12).contains(&hour) {
+        Err("Hour out of bounds for a 12-hour clock")?;
+      }
+      hour = match (hour, pm) {
+        (12, false) => 0,
+        (12, true) => 12,
+        (h, true) => h + 12,
+        (h, false) => h,
+      };
+    } else if hour >= 24 {
+      Err("Hours out of bounds.")?;
+    }
+    if minute >= 60 {
+      Err("Minutes out of bounds.")?;
+    }
+    if second >= 60 {
+      Err("Seconds out of bounds.")?;
+    }
+    Ok(Self::new_with_nanos(hour, minute, second, nanos))
+  }
+
+  /// Formats this wall-clock time as a zero-padded 12-hour clock string with an `AM`/`PM` suffix,
+  /// e.g. `"03:30:00 PM"`.
+  ///
+  /// ## Examples
+  ///
+  /// ```
+  /// # use wall_clock::time;
+  /// assert_eq!(time!(15:30:00).to_12_hour_string(), "03:30:00 PM");
+  /// ```
+  pub fn to_12_hour_string(&self) -> String {
+    self.format("%I:%M:%S %p")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+
+  use crate::time;
+  use crate::SubsecondFormat;
+  use crate::WallClockTime;
+
+  #[test]
+  fn test_format_24_hour() {
+    check!(time!(15:04:05).format("%H:%M:%S") == "15:04:05");
+    check!(time!(09:04:05).format("%H:%M") == "09:04");
+  }
+
+  #[test]
+  fn test_format_12_hour() {
+    check!(time!(15:04:05).format("%I:%M %p") == "03:04 PM");
+    check!(time!(00:04:05).format("%I:%M %p") == "12:04 AM");
+    check!(time!(12:04:05).format("%I:%M %p") == "12:04 PM");
+  }
+
+  #[test]
+  fn test_format_fixed_frac() {
+    let t = WallClockTime::new_with_micros(15, 4, 5, 123_456);
+    check!(t.format("%H:%M:%S%.3f") == "15:04:05.123");
+    check!(t.format("%H:%M:%S%.6f") == "15:04:05.123456");
+    check!(t.format("%H:%M:%S%.9f") == "15:04:05.123456000");
+    check!(t.format("%H:%M:%S.%f") == "15:04:05.123456");
+  }
+
+  #[test]
+  fn test_parse_from_str_24_hour() {
+    check!(WallClockTime::parse_from_str("15:04:05", "%H:%M:%S") == Ok(time!(15:04:05)));
+    check!(WallClockTime::parse_from_str("15:04", "%H:%M") == Ok(time!(15:04:00)));
+  }
+
+  #[test]
+  fn test_parse_from_str_12_hour() {
+    check!(WallClockTime::parse_from_str("03:04 PM", "%I:%M %p") == Ok(time!(15:04:00)));
+    check!(WallClockTime::parse_from_str("12:04 AM", "%I:%M %p") == Ok(time!(00:04:00)));
+    check!(WallClockTime::parse_from_str("12:04 PM", "%I:%M %p") == Ok(time!(12:04:00)));
+  }
+
+  #[test]
+  fn test_parse_from_str_fixed_frac() {
+    check!(
+      WallClockTime::parse_from_str("15:04:05.123", "%H:%M:%S%.3f")
+        == Ok(WallClockTime::new_with_micros(15, 4, 5, 123_000))
+    );
+    check!(
+      WallClockTime::parse_from_str("15:04:05.123456", "%H:%M:%S%.6f")
+        == Ok(WallClockTime::new_with_micros(15, 4, 5, 123_456))
+    );
+  }
+
+  #[test]
+  fn test_parse_from_str_invalid() {
+    check!(WallClockTime::parse_from_str("not a time", "%H:%M:%S").is_err());
+    check!(WallClockTime::parse_from_str("15:04:05extra", "%H:%M:%S").is_err());
+  }
+
+  #[test]
+  fn test_parse_from_str_out_of_range() {
+    check!(WallClockTime::parse_from_str("99:00:00", "%H:%M:%S").is_err());
+    check!(WallClockTime::parse_from_str("00:99:00", "%H:%M:%S").is_err());
+    check!(WallClockTime::parse_from_str("00:00:99", "%H:%M:%S").is_err());
+    check!(WallClockTime::parse_from_str("13:04 PM", "%I:%M %p").is_err());
+  }
+
+  #[test]
+  fn test_parse_from_str_fixed_frac_requires_exact_digits() {
+    check!(WallClockTime::parse_from_str("15:04:05.1", "%H:%M:%S%.3f").is_err());
+    check!(WallClockTime::parse_from_str("15:04:05.12", "%H:%M:%S%.3f").is_err());
+  }
+
+  #[test]
+  fn test_display_with() {
+    let t = WallClockTime::new_with_nanos(15, 4, 5, 123_000_000);
+    check!(t.display_with(SubsecondFormat::None) == "15:04:05");
+    check!(t.display_with(SubsecondFormat::Millis) == "15:04:05.123");
+    check!(t.display_with(SubsecondFormat::Micros) == "15:04:05.123000");
+    check!(t.display_with(SubsecondFormat::Nanos) == "15:04:05.123000000");
+    check!(t.display_with(SubsecondFormat::AutoTrim) == "15:04:05.123");
+    check!(time!(15:04:05).display_with(SubsecondFormat::AutoTrim) == "15:04:05");
+  }
+
+  #[test]
+  fn test_to_12_hour_string() {
+    check!(time!(15:30:00).to_12_hour_string() == "03:30:00 PM");
+    check!(time!(00:30:00).to_12_hour_string() == "12:30:00 AM");
+    check!(time!(12:30:00).to_12_hour_string() == "12:30:00 PM");
+  }
+}
@@ -1,3 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
 //! `wall-clock` provides a simple and very basic struct for repsenting time as one reads it off a
 //! clock on the wall, e.g. with no concept of date, or time zone.
 //!
@@ -22,17 +24,43 @@
 //!
 //! `wall-clock` ships with the following features:
 //!
-//! - **diesel-pg**: Enables interop with PostgreSQL `TIME` columns using Diesel.
-//! - **serde**: Enables serialization and deserialization with `serde`. _(Enabled by default.)_
+//! - **chrono**: Enables `From`/`TryFrom` conversions to/from `chrono::NaiveTime`.
+//! - **diesel-pg**: Enables interop with PostgreSQL `TIME` columns using Diesel. Implies `std`.
+//! - **serde**: Enables serialization and deserialization with `serde`. Implies `std`.
+//!   _(Enabled by default.)_
+//! - **std**: Enables the standard library. Without it, the crate is `#![no_std]`:
+//!   [`WallClockTime`] still supports construction, arithmetic, [`Display`], and [`FromStr`]
+//!   parsing, which is enough for embedded firmware that reads a clock peripheral but has no
+//!   allocator. The `strftime`-style formatting in [`WallClockTime::format`] and
+//!   [`WallClockTime::display_with`] allocate a `String` and so require this feature.
+//!   _(Enabled by default.)_
+//! - **time**: Enables `From`/`TryFrom` conversions to/from the `time` crate's `Time`.
+
+use core::fmt::Debug;
+use core::fmt::Display;
+use core::ops::Add;
+use core::ops::AddAssign;
+use core::ops::Sub;
+use core::ops::SubAssign;
+use core::str::FromStr;
+use core::time::Duration;
 
-use std::fmt::Debug;
-use std::fmt::Display;
-use std::str::FromStr;
+/// The number of nanoseconds in a full day, used to wrap arithmetic around midnight.
+const NANOS_PER_DAY: i64 = 86_400_000_000_000;
 
+#[cfg(feature = "chrono")]
+mod chrono;
 #[cfg(feature = "diesel-pg")]
 mod db;
+#[cfg(feature = "std")]
+mod format;
 #[cfg(feature = "serde")]
 mod serde;
+#[cfg(feature = "time")]
+mod timelib;
+
+#[cfg(feature = "std")]
+pub use format::SubsecondFormat;
 
 /// A representation of a time, as read from a wall clock, independent of date or time zone.
 #[derive(Clone, Copy, Default, Eq, PartialEq, PartialOrd, Ord)]
@@ -41,8 +69,12 @@ mod serde;
 pub struct WallClockTime {
   /// The number of seconds elapsed since midnight.
   seconds: u32,
-  /// The number of microseconds elapsed since `seconds`.
-  micros: u32,
+  /// The number of nanoseconds elapsed since `seconds`.
+  nanos: u32,
+  /// Whether this time represents a leap second (`:60`), constructed via [`Self::try_new`] or
+  /// [`Self::parse_lenient`]. A wall clock has no date, so a leap second can't be validated; this
+  /// is just a marker that the input specified one.
+  leap: bool,
 }
 
 impl WallClockTime {
@@ -51,7 +83,8 @@ impl WallClockTime {
   /// ## Panic
   ///
   /// Panics if any values are too high for a wall clock (hours >= 24, minutes >= 60, seconds >=
-  /// 60). Wall clocks don't know about leap seconds.
+  /// 60). Wall clocks don't know about leap seconds; see [`Self::try_new`] for a fallible
+  /// alternative that tolerates a 60th second.
   pub const fn new(hours: u8, minutes: u8, seconds: u8) -> Self {
     Self::new_with_micros(hours, minutes, seconds, 0)
   }
@@ -63,11 +96,22 @@ impl WallClockTime {
   /// Panics if any values are too high for a wall clock (hours >= 24, minutes >= 60, seconds >=
   /// 60). Wall clocks don't know about leap seconds.
   pub const fn new_with_micros(hours: u8, minutes: u8, seconds: u8, micros: u32) -> Self {
+    assert!(micros < 1_000_000, "Microseconds out of bounds.");
+    Self::new_with_nanos(hours, minutes, seconds, micros * 1_000)
+  }
+
+  /// A new wall-clock time set to the provided hours, minutes, seconds, and nanoseconds.
+  ///
+  /// ## Panic
+  ///
+  /// Panics if any values are too high for a wall clock (hours >= 24, minutes >= 60, seconds >=
+  /// 60, nanos >= 1,000,000,000). Wall clocks don't know about leap seconds.
+  pub const fn new_with_nanos(hours: u8, minutes: u8, seconds: u8, nanos: u32) -> Self {
     assert!(hours < 24, "Hours out of bounds.");
     assert!(minutes < 60, "Minutes out of bounds.");
     assert!(seconds < 60, "Seconds out of bounds.");
-    assert!(micros < 1_000_000, "Microseconds out of bounds.");
-    Self { seconds: hours as u32 * 3_600 + minutes as u32 * 60 + seconds as u32, micros }
+    assert!(nanos < 1_000_000_000, "Nanoseconds out of bounds.");
+    Self { seconds: hours as u32 * 3_600 + minutes as u32 * 60 + seconds as u32, nanos, leap: false }
   }
 
   /// A new wall-clock time corresponding to the number of seconds and microseconds offset from
@@ -80,7 +124,51 @@ impl WallClockTime {
   pub const fn new_midnight_offset(seconds: u32, micros: u32) -> Self {
     assert!(seconds < 86_400, "Seconds out of bounds.");
     assert!(micros < 1_000_000, "Microseconds out of bounds.");
-    Self { seconds, micros }
+    Self { seconds, nanos: micros * 1_000, leap: false }
+  }
+
+  /// A fallible version of [`Self::new`] that, instead of panicking, additionally accepts a 60th
+  /// second (`seconds == 60`) to tolerate leap seconds. Since a wall clock has no date, a leap
+  /// second can't be validated; it's stored as second `59` (keeping its subsecond part) and marked
+  /// so that [`Self::is_leap_second`] and [`Self::second`] can still report it as `60`.
+  ///
+  /// Returns an error if `hours >= 24`, `minutes >= 60`, or `seconds > 60`.
+  pub const fn try_new(hours: u8, minutes: u8, seconds: u8) -> Result<Self, &'static str> {
+    Self::try_new_with_nanos(hours, minutes, seconds, 0)
+  }
+
+  /// A fallible version of [`Self::new_with_nanos`] that tolerates a leap second the same way
+  /// [`Self::try_new`] does.
+  pub const fn try_new_with_nanos(
+    hours: u8,
+    minutes: u8,
+    seconds: u8,
+    nanos: u32,
+  ) -> Result<Self, &'static str> {
+    if hours >= 24 {
+      return Err("Hours out of bounds.");
+    }
+    if minutes >= 60 {
+      return Err("Minutes out of bounds.");
+    }
+    if nanos >= 1_000_000_000 {
+      return Err("Nanoseconds out of bounds.");
+    }
+    let (clamped_seconds, leap) = match seconds {
+      0..=59 => (seconds as u32, false),
+      60 => (59, true),
+      _ => return Err("Seconds out of bounds."),
+    };
+    Ok(Self {
+      seconds: hours as u32 * 3_600 + minutes as u32 * 60 + clamped_seconds,
+      nanos,
+      leap,
+    })
+  }
+
+  /// Whether this wall-clock time represents a leap second (`:60`).
+  pub const fn is_leap_second(&self) -> bool {
+    self.leap
   }
 
   /// The number of hours since midnight.
@@ -93,28 +181,92 @@ impl WallClockTime {
     (self.seconds % 3600 / 60) as u8
   }
 
-  /// The number of seconds since the last minute.
+  /// The number of seconds since the last minute. Returns `60` if this is a leap second (see
+  /// [`Self::is_leap_second`]).
   pub const fn second(&self) -> u8 {
-    (self.seconds % 60) as u8
+    if self.leap { 60 } else { (self.seconds % 60) as u8 }
   }
 
   /// The number of microseconds since the last second.
   pub const fn microsecond(&self) -> u32 {
-    self.micros
+    self.nanos / 1_000
+  }
+
+  /// The number of nanoseconds since the last second.
+  pub const fn nanosecond(&self) -> u32 {
+    self.nanos
+  }
+
+  /// The number of nanoseconds elapsed since midnight.
+  const fn nanos_since_midnight(&self) -> i64 {
+    self.seconds as i64 * 1_000_000_000 + self.nanos as i64
+  }
+
+  /// Constructs a wall-clock time from a number of nanoseconds since midnight, wrapping modulo 24
+  /// hours (so both negative values and values past the end of the day are handled).
+  const fn from_nanos_since_midnight(nanos: i64) -> Self {
+    let wrapped = nanos.rem_euclid(NANOS_PER_DAY);
+    Self {
+      seconds: (wrapped / 1_000_000_000) as u32,
+      nanos: (wrapped % 1_000_000_000) as u32,
+      leap: false,
+    }
+  }
+
+  /// The forward, wrapping distance from `other` to `self`.
+  ///
+  /// Since a wall clock has no date, `self` is always treated as occurring at or after `other`,
+  /// wrapping past midnight if necessary. For example, the duration from `23:59:50` to
+  /// `00:00:10` is 20 seconds, not a negative duration.
+  pub const fn duration_since(&self, other: Self) -> Duration {
+    let delta = (self.nanos_since_midnight() - other.nanos_since_midnight()).rem_euclid(NANOS_PER_DAY);
+    Duration::from_nanos(delta as u64)
+  }
+}
+
+impl Add<Duration> for WallClockTime {
+  type Output = Self;
+
+  /// Adds a [`Duration`] to this wall-clock time, wrapping around midnight.
+  fn add(self, rhs: Duration) -> Self::Output {
+    Self::from_nanos_since_midnight(self.nanos_since_midnight() + rhs.as_nanos() as i64)
+  }
+}
+
+impl Sub<Duration> for WallClockTime {
+  type Output = Self;
+
+  /// Subtracts a [`Duration`] from this wall-clock time, wrapping around midnight.
+  ///
+  /// For example, `time!(00:00:10) - Duration::from_secs(20)` is `23:59:50`.
+  fn sub(self, rhs: Duration) -> Self::Output {
+    Self::from_nanos_since_midnight(self.nanos_since_midnight() - rhs.as_nanos() as i64)
+  }
+}
+
+impl AddAssign<Duration> for WallClockTime {
+  fn add_assign(&mut self, rhs: Duration) {
+    *self = *self + rhs;
+  }
+}
+
+impl SubAssign<Duration> for WallClockTime {
+  fn sub_assign(&mut self, rhs: Duration) {
+    *self = *self - rhs;
   }
 }
 
 impl Debug for WallClockTime {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
     Display::fmt(self, f)
   }
 }
 
 impl Display for WallClockTime {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
     write!(f, "{:02}:{:02}:{:02}", self.hour(), self.minute(), self.second())?;
-    if self.micros > 0 {
-      write!(f, ".{:06}", self.micros)?;
+    if self.microsecond() > 0 {
+      write!(f, ".{:06}", self.microsecond())?;
     }
     Ok(())
   }
@@ -124,26 +276,145 @@ impl FromStr for WallClockTime {
   type Err = &'static str;
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
-    let seconds_micros: Vec<&str> = s.split('.').collect();
-    if seconds_micros.len() > 2 {
-      Err("Only one `.` allowed in wall-clock times")?;
+    if let Some(suffix) = s.get(s.len().saturating_sub(2)..) {
+      if suffix.eq_ignore_ascii_case("am") || suffix.eq_ignore_ascii_case("pm") {
+        return parse_12_hour(s, suffix.eq_ignore_ascii_case("pm"));
+      }
     }
-    let micros = match seconds_micros.get(1) {
-      Some(micros) => micros.parse::<u32>().map_err(|_| "Invalid microseconds")?,
-      None => 0,
-    };
-    let hms = seconds_micros.first().ok_or("Empty string")?;
-    let hms: Vec<&str> = hms.split(':').collect();
-    if hms.len() != 3 {
-      Err("Invalid HH:MM:SS specified")?;
+    let (hours, minutes, seconds, nanos) = scan_hms(s)?;
+    if hours >= 24 {
+      Err("Hours out of bounds.")?;
     }
-    let hours = hms[0].parse::<u32>().map_err(|_| "Invalid HH")?;
-    let minutes = hms[1].parse::<u32>().map_err(|_| "Invalid MM")?;
-    let seconds = hms[2].parse::<u32>().map_err(|_| "Invalid SS")?;
-    Ok(Self { seconds: hours * 3600 + minutes * 60 + seconds, micros })
+    if minutes >= 60 {
+      Err("Minutes out of bounds.")?;
+    }
+    if seconds >= 60 {
+      Err("Seconds out of bounds.")?;
+    }
+    Ok(Self { seconds: hours * 3600 + minutes * 60 + seconds, nanos, leap: false })
+  }
+}
+
+impl WallClockTime {
+  /// Parses a wall-clock time the same way [`FromStr`] does, but additionally tolerates a 60th
+  /// second (`HH:MM:60[.ffffff]`), as permitted by RFC 3339 partial-time, since a leap second
+  /// can't be validated without a full date. The resulting time reports [`Self::is_leap_second`].
+  pub fn parse_lenient(s: &str) -> Result<Self, &'static str> {
+    let (hours, minutes, seconds, nanos) = scan_hms(s)?;
+    let hours = u8::try_from(hours).map_err(|_| "Invalid HH")?;
+    let minutes = u8::try_from(minutes).map_err(|_| "Invalid MM")?;
+    let seconds = u8::try_from(seconds).map_err(|_| "Invalid SS")?;
+    Self::try_new_with_nanos(hours, minutes, seconds, nanos)
   }
 }
 
+/// Scans an `HH:MM:SS[.ffffff]` field by hand, byte by byte, instead of collecting a `Vec` of
+/// `split` fields, so parsing doesn't need an allocator and works in `no_std` environments.
+///
+/// Returns the hour, minute, second, and nanosecond components. As before, the fractional part
+/// (if present) is read as a plain integer and scaled as if it were always 6 digits.
+fn scan_hms(s: &str) -> Result<(u32, u32, u32, u32), &'static str> {
+  let bytes = s.as_bytes();
+  let mut i = 0;
+  let hours = scan_digits(bytes, &mut i).ok_or("Invalid HH")?;
+  expect_byte(bytes, &mut i, b':').ok_or("Invalid HH:MM:SS specified")?;
+  let minutes = scan_digits(bytes, &mut i).ok_or("Invalid MM")?;
+  expect_byte(bytes, &mut i, b':').ok_or("Invalid HH:MM:SS specified")?;
+  let seconds = scan_digits(bytes, &mut i).ok_or("Invalid SS")?;
+  let nanos = match bytes.get(i) {
+    None => 0,
+    Some(b'.') => {
+      i += 1;
+      // Capped at 9 digits so the digits always parse into a `u32`. They're read as microseconds,
+      // so a value of 1,000,000 or more is out of range and rejected below.
+      let micros = scan_digits_capped(bytes, &mut i, 9).ok_or("Invalid microseconds")?;
+      if bytes.get(i) == Some(&b'.') {
+        Err("Only one `.` allowed in wall-clock times")?;
+      }
+      if i != bytes.len() {
+        Err("Invalid microseconds")?;
+      }
+      if micros >= 1_000_000 {
+        Err("Invalid microseconds")?;
+      }
+      micros * 1_000
+    }
+    Some(_) => Err("Invalid HH:MM:SS specified")?,
+  };
+  Ok((hours, minutes, seconds, nanos))
+}
+
+/// Scans a run of ASCII digits starting at `*i`, advancing `*i` past them, and parses them as a
+/// `u32`. Returns `None` if there's no digit at the current position or the digits overflow.
+fn scan_digits(bytes: &[u8], i: &mut usize) -> Option<u32> {
+  scan_digits_capped(bytes, i, usize::MAX)
+}
+
+/// Like [`scan_digits`], but scans at most `max` digits, leaving any further digit characters
+/// unconsumed for the caller to reject.
+fn scan_digits_capped(bytes: &[u8], i: &mut usize, max: usize) -> Option<u32> {
+  let start = *i;
+  while *i < bytes.len() && *i - start < max && bytes[*i].is_ascii_digit() {
+    *i += 1;
+  }
+  if *i == start {
+    return None;
+  }
+  core::str::from_utf8(&bytes[start..*i]).ok()?.parse().ok()
+}
+
+/// Consumes `byte` at `*i` if present, advancing `*i` past it. Returns `None` if the byte at the
+/// current position doesn't match, including if `*i` is already past the end of the string.
+fn expect_byte(bytes: &[u8], i: &mut usize, byte: u8) -> Option<()> {
+  if bytes.get(*i) == Some(&byte) {
+    *i += 1;
+    Some(())
+  } else {
+    None
+  }
+}
+
+/// Parses a 12-hour clock string of the form `H:MM[:SS] AM`/`H:MM[:SS] PM`. The caller has
+/// already identified the trailing `AM`/`PM` suffix and tells us which one it was via `pm`.
+fn parse_12_hour(s: &str, pm: bool) -> Result<WallClockTime, &'static str> {
+  let time_part = s[..s.len() - 2].trim_end();
+  let bytes = time_part.as_bytes();
+  let mut i = 0;
+  let hour = scan_digits(bytes, &mut i).ok_or("Invalid HH")?;
+  expect_byte(bytes, &mut i, b':').ok_or("Invalid H:MM[:SS] specified")?;
+  let minute = scan_digits(bytes, &mut i).ok_or("Invalid MM")?;
+  let seconds = match bytes.get(i) {
+    None => 0,
+    Some(b':') => {
+      i += 1;
+      scan_digits(bytes, &mut i).ok_or("Invalid SS")?
+    }
+    Some(_) => Err("Invalid H:MM[:SS] specified")?,
+  };
+  if i != bytes.len() {
+    Err("Invalid H:MM[:SS] specified")?;
+  }
+  let hour = u8::try_from(hour).map_err(|_| "Invalid HH")?;
+  let minute = u8::try_from(minute).map_err(|_| "Invalid MM")?;
+  let seconds = u8::try_from(seconds).map_err(|_| "Invalid SS")?;
+  if !(1..=12).contains(&hour) {
+    Err("Hour out of bounds for a 12-hour clock")?;
+  }
+  if minute >= 60 {
+    Err("Minutes out of bounds.")?;
+  }
+  if seconds >= 60 {
+    Err("Seconds out of bounds.")?;
+  }
+  let hour24 = match (hour, pm) {
+    (12, false) => 0,
+    (12, true) => 12,
+    (h, true) => h + 12,
+    (h, false) => h,
+  };
+  Ok(WallClockTime::new(hour24, minute, seconds))
+}
+
 /// Construct a wall clock time from a `HH:MM:SS` literal.
 ///
 /// ## Examples
@@ -167,6 +438,8 @@ macro_rules! time {
 
 #[cfg(test)]
 mod tests {
+  use std::time::Duration;
+
   use assert2::check;
 
   use crate::WallClockTime;
@@ -197,6 +470,14 @@ mod tests {
     check!(WallClockTime::new_with_micros(17, 15, 30, 600_000).microsecond() == 600_000);
   }
 
+  #[test]
+  fn test_nanos() {
+    check!(WallClockTime::new_with_nanos(9, 30, 0, 0).nanosecond() == 0);
+    check!(WallClockTime::new_with_nanos(17, 15, 30, 600_000_123).nanosecond() == 600_000_123);
+    check!(WallClockTime::new_with_nanos(17, 15, 30, 600_000_123).microsecond() == 600_000);
+    check!(WallClockTime::new_with_micros(17, 15, 30, 600_000).nanosecond() == 600_000_000);
+  }
+
   #[test]
   fn test_display() {
     check!(time!(16:00:00).to_string() == "16:00:00");
@@ -215,4 +496,84 @@ mod tests {
     );
     Ok(())
   }
+
+  #[test]
+  fn test_parse_out_of_range() {
+    check!("25:99:99".parse::<WallClockTime>().is_err());
+    check!("24:00:00".parse::<WallClockTime>().is_err());
+    check!("00:60:00".parse::<WallClockTime>().is_err());
+    check!("00:00:61".parse::<WallClockTime>().is_err());
+  }
+
+  #[test]
+  fn test_parse_long_fraction_does_not_panic() {
+    check!("12:00:00.123456789".parse::<WallClockTime>().is_err());
+    check!("12:00:00.1234567890".parse::<WallClockTime>().is_err());
+  }
+
+  #[test]
+  fn test_parse_12_hour() -> Result<(), &'static str> {
+    check!("3:30:00 PM".parse::<WallClockTime>()? == time!(15:30:00));
+    check!("3:30:00 AM".parse::<WallClockTime>()? == time!(03:30:00));
+    check!("12:00:00 AM".parse::<WallClockTime>()? == time!(00:00:00));
+    check!("12:00:00 PM".parse::<WallClockTime>()? == time!(12:00:00));
+    check!("3:30 pm".parse::<WallClockTime>()? == time!(15:30:00));
+    check!("13:00:00 PM".parse::<WallClockTime>().is_err());
+    check!("3:99:00 PM".parse::<WallClockTime>().is_err());
+    check!("3:00:99 PM".parse::<WallClockTime>().is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_try_new_leap_second() -> Result<(), &'static str> {
+    let t = WallClockTime::try_new(23, 59, 60)?;
+    check!(t.is_leap_second());
+    check!(t.hour() == 23);
+    check!(t.minute() == 59);
+    check!(t.second() == 60);
+    check!(t.to_string() == "23:59:60");
+    check!(!time!(23:59:59).is_leap_second());
+    Ok(())
+  }
+
+  #[test]
+  fn test_try_new_bounds() {
+    check!(WallClockTime::try_new(24, 0, 0).is_err());
+    check!(WallClockTime::try_new(0, 60, 0).is_err());
+    check!(WallClockTime::try_new(0, 0, 61).is_err());
+  }
+
+  #[test]
+  fn test_parse_lenient() -> Result<(), &'static str> {
+    check!(WallClockTime::parse_lenient("09:30:00")? == time!(09:30:00));
+    let leap = WallClockTime::parse_lenient("23:59:60")?;
+    check!(leap.is_leap_second());
+    check!(leap.second() == 60);
+    check!(WallClockTime::parse_lenient("23:59:61").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_add_sub_duration() {
+    check!(time!(15:00:00) + Duration::from_secs(30) == time!(15:00:30));
+    check!(time!(23:59:50) + Duration::from_secs(20) == time!(00:00:10));
+    check!(time!(15:00:30) - Duration::from_secs(30) == time!(15:00:00));
+    check!(time!(00:00:10) - Duration::from_secs(20) == time!(23:59:50));
+  }
+
+  #[test]
+  fn test_add_assign_sub_assign_duration() {
+    let mut t = time!(15:00:00);
+    t += Duration::from_secs(30);
+    check!(t == time!(15:00:30));
+    t -= Duration::from_secs(30);
+    check!(t == time!(15:00:00));
+  }
+
+  #[test]
+  fn test_duration_since() {
+    check!(time!(15:00:30).duration_since(time!(15:00:00)) == Duration::from_secs(30));
+    check!(time!(00:00:10).duration_since(time!(23:59:50)) == Duration::from_secs(20));
+    check!(time!(12:00:00).duration_since(time!(12:00:00)) == Duration::from_secs(0));
+  }
 }
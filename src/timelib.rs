@@ -0,0 +1,54 @@
+//! Conversions to/from the `time` crate's `Time`.
+
+use time::Time;
+
+use crate::WallClockTime;
+
+impl From<WallClockTime> for Time {
+  /// Converts a [`WallClockTime`] into a `time` crate [`Time`].
+  ///
+  /// The `time` crate has no representation for leap seconds, so a leap second (see
+  /// [`WallClockTime::is_leap_second`]) is clamped to second `59`.
+  fn from(t: WallClockTime) -> Self {
+    Time::from_hms_nano(t.hour(), t.minute(), t.second().min(59), t.nanosecond())
+      .expect("a WallClockTime always maps to a valid Time")
+  }
+}
+
+impl TryFrom<Time> for WallClockTime {
+  type Error = &'static str;
+
+  /// Converts a `time` crate [`Time`] into a [`WallClockTime`].
+  fn try_from(t: Time) -> Result<Self, Self::Error> {
+    WallClockTime::try_new_with_nanos(t.hour(), t.minute(), t.second(), t.nanosecond())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+  use time::Time;
+
+  use crate::time as wall_clock_time;
+  use crate::WallClockTime;
+
+  #[test]
+  fn test_to_time() {
+    let t: Time = wall_clock_time!(15:04:05).into();
+    check!(t == Time::from_hms(15, 4, 5).unwrap());
+  }
+
+  #[test]
+  fn test_from_time() -> Result<(), &'static str> {
+    let t = Time::from_hms(15, 4, 5).unwrap();
+    check!(WallClockTime::try_from(t)? == wall_clock_time!(15:04:05));
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_rejects_hour_outside_time_range() {
+    // `WallClockTime`'s public constructors and parsers all enforce `hour < 24`, so there's no
+    // way to reach `From<WallClockTime> for Time` with an out-of-range value to convert.
+    check!("25:00:00".parse::<WallClockTime>().is_err());
+  }
+}